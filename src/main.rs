@@ -4,6 +4,11 @@ use std::collections::HashMap;
 use num_bigint::BigUint;
 use num_traits::{Pow, ToPrimitive, Zero};
 
+mod gen;
+mod rlp;
+mod text;
+mod universal;
+
 pub type Label = usize;
 pub type Register = u64;
 pub type State = (Label, HashMap<Register, BigUint>);
@@ -16,29 +21,249 @@ pub enum Instruction {
 }
 
 use Instruction::*;
+
+/// At or above this register index, a dense `Vec` store would allocate more than
+/// is reasonable just to hold one far-out register, so [`RegisterFile`]
+/// falls back to the original sparse `HashMap` representation.
+const DENSE_REGISTER_LIMIT: u64 = 1 << 20;
+
+/// The evaluator's register store. `eval_program` used to do
+/// two or three `HashMap` lookups per instruction, which dominates runtime
+/// for long-running machines over large `BigUint` registers. Most programs
+/// only ever touch a small, contiguous range of registers, so this indexes
+/// a dense `Vec` directly in that common case and only falls back to
+/// hashing when the register set is too large to allocate densely.
+///
+/// `Dense` also tracks, per index, whether that register has been read or
+/// written during this run (`touched`) — the old `HashMap`-based evaluator
+/// implicitly recorded this via `entry().or_insert_with(..)` on every
+/// access, so the register showed up in the output `State` even if it
+/// never left zero. Registers that a program merely *refers to* without
+/// ever reaching at runtime must stay absent from the output, just as they
+/// were before.
+enum RegisterFile {
+    Dense {
+        registers: Vec<BigUint>,
+        touched: Vec<bool>,
+    },
+    Sparse(HashMap<Register, BigUint>),
+}
+
+impl RegisterFile {
+    /// Build a store sized to fit every register the program refers to and
+    /// every register already present in `initial`.
+    fn new(program: &[Instruction], initial: &HashMap<Register, BigUint>) -> Self {
+        let max_index = program
+            .iter()
+            .filter_map(|instr| match instr {
+                Add(r, _) | Sub(r, _, _) => Some(*r),
+                Halt => None,
+            })
+            .chain(initial.keys().copied())
+            .max();
+
+        match max_index {
+            Some(max) if max < DENSE_REGISTER_LIMIT => {
+                let len = max as usize + 1;
+                let mut registers = vec![BigUint::zero(); len];
+                let mut touched = vec![false; len];
+                for (register, value) in initial {
+                    registers[*register as usize] = value.clone();
+                    touched[*register as usize] = true;
+                }
+                RegisterFile::Dense { registers, touched }
+            }
+            _ => RegisterFile::Sparse(initial.clone()),
+        }
+    }
+
+    /// Grow `registers`/`touched` so index `r` is valid.
+    fn ensure_len(registers: &mut Vec<BigUint>, touched: &mut Vec<bool>, r: Register) {
+        if r as usize >= registers.len() {
+            registers.resize(r as usize + 1, BigUint::zero());
+            touched.resize(r as usize + 1, false);
+        }
+    }
+
+    fn is_zero(&mut self, r: Register) -> bool {
+        match self {
+            RegisterFile::Dense { registers, touched } => {
+                Self::ensure_len(registers, touched, r);
+                touched[r as usize] = true;
+                registers[r as usize].is_zero()
+            }
+            RegisterFile::Sparse(registers) => registers.entry(r).or_insert_with(BigUint::zero).is_zero(),
+        }
+    }
+
+    fn increment(&mut self, r: Register) {
+        match self {
+            RegisterFile::Dense { registers, touched } => {
+                Self::ensure_len(registers, touched, r);
+                touched[r as usize] = true;
+                registers[r as usize] += 1u32;
+            }
+            RegisterFile::Sparse(registers) => {
+                *registers.entry(r).or_insert_with(BigUint::zero) += 1u32;
+            }
+        }
+    }
+
+    fn decrement(&mut self, r: Register) {
+        match self {
+            RegisterFile::Dense { registers, touched } => {
+                Self::ensure_len(registers, touched, r);
+                touched[r as usize] = true;
+                registers[r as usize] -= 1u32;
+            }
+            RegisterFile::Sparse(registers) => {
+                *registers.entry(r).or_insert_with(BigUint::zero) -= 1u32;
+            }
+        }
+    }
+
+    /// The logical `(label, registers)` configuration, as a value that's
+    /// fully `Hash`/`Eq` rather than a lossy digest of one — so storing it
+    /// in a `HashMap` can never mistake two distinct configurations for the
+    /// same one on a hash collision. Zero-valued registers are dropped so
+    /// dense and sparse stores produce the same key for the same
+    /// configuration.
+    fn configuration_key(&self, label: Label) -> (Label, Vec<(Register, BigUint)>) {
+        let mut nonzero: Vec<(Register, BigUint)> = match self {
+            RegisterFile::Dense { registers, .. } => registers
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| !v.is_zero())
+                .map(|(i, v)| (i as Register, v.clone()))
+                .collect(),
+            RegisterFile::Sparse(registers) => registers
+                .iter()
+                .filter(|(_, v)| !v.is_zero())
+                .map(|(r, v)| (*r, v.clone()))
+                .collect(),
+        };
+        nonzero.sort_by_key(|(r, _)| *r);
+
+        (label, nonzero)
+    }
+
+    /// Convert back to the public, sparse `State` representation — only
+    /// registers that were actually touched during the run appear, mirroring
+    /// what the old `HashMap`-based evaluator would have produced.
+    fn into_map(self) -> HashMap<Register, BigUint> {
+        match self {
+            RegisterFile::Dense { registers, touched } => registers
+                .into_iter()
+                .zip(touched)
+                .enumerate()
+                .filter(|(_, (_, touched))| *touched)
+                .map(|(i, (value, _))| (i as Register, value))
+                .collect(),
+            RegisterFile::Sparse(registers) => registers,
+        }
+    }
+}
+
 pub fn eval_program(program: &[Instruction], state: &State) -> State {
-    let mut new_state = state.clone();
+    let mut label = state.0;
+    let mut registers = RegisterFile::new(program, &state.1);
+
+    while label < program.len() {
+        match program[label] {
+            Add(r, l) => {
+                registers.increment(r);
+                label = l;
+            }
+            Sub(r, l1, l2) => {
+                if registers.is_zero(r) {
+                    label = l2;
+                } else {
+                    registers.decrement(r);
+                    label = l1;
+                }
+            }
+            Halt => return (label, registers.into_map()),
+        }
+    }
+
+    (label, registers.into_map())
+}
+
+/// Outcome of a bounded run started by [`run_traced`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum RunResult {
+    /// The program hit `Halt` with the machine in this final state.
+    Halted(State),
+    /// Execution jumped to a label outside the program.
+    OutOfBounds(State),
+    /// `max_steps` instructions executed without halting.
+    StepLimitReached(State),
+    /// The exact same `(Label, registers)` configuration was seen twice,
+    /// which proves the machine never halts.
+    LoopDetected(State),
+}
+
+/// One executed instruction, as `(step_index, label, instruction)`.
+pub type TraceEntry = (usize, Label, Instruction);
+
+/// Run `program` from `state` for at most `max_steps` instructions.
+///
+/// Unlike [`eval_program`], this never loops forever: it reports
+/// [`RunResult::OutOfBounds`] or [`RunResult::StepLimitReached`] instead of
+/// hanging, and detects simple self-loops (a repeated `(Label, registers)`
+/// configuration) as [`RunResult::LoopDetected`]. When `capture_trace` is
+/// set, the second element of the return value holds every instruction
+/// executed, in order.
+pub fn run_traced(
+    program: &[Instruction],
+    state: &State,
+    max_steps: usize,
+    capture_trace: bool,
+) -> (RunResult, Option<Vec<TraceEntry>>) {
+    let mut label = state.0;
+    let mut registers = RegisterFile::new(program, &state.1);
+    let mut trace = capture_trace.then(Vec::new);
+    let mut seen_configurations: HashMap<(Label, Vec<(Register, BigUint)>), usize> =
+        HashMap::new();
+
+    for step in 0..max_steps {
+        if label >= program.len() {
+            return (RunResult::OutOfBounds((label, registers.into_map())), trace);
+        }
+
+        if seen_configurations
+            .insert(registers.configuration_key(label), step)
+            .is_some()
+        {
+            return (RunResult::LoopDetected((label, registers.into_map())), trace);
+        }
+
+        let curr_instr = program[label];
+        if let Some(trace) = trace.as_mut() {
+            trace.push((step, label, curr_instr));
+        }
 
-    while new_state.0 < program.len() {
-        let curr_instr = program[new_state.0];
         match curr_instr {
             Add(r, l) => {
-                *(new_state.1.entry(r).or_insert_with(BigUint::zero)) += 1u32;
-                new_state.0 = l;
+                registers.increment(r);
+                label = l;
             }
             Sub(r, l1, l2) => {
-                if new_state.1.entry(r).or_insert_with(BigUint::zero).is_zero() {
-                    new_state.0 = l2;
+                if registers.is_zero(r) {
+                    label = l2;
                 } else {
-                    *(new_state.1.entry(r).or_insert_with(BigUint::zero)) -= 1u32;
-                    new_state.0 = l1;
+                    registers.decrement(r);
+                    label = l1;
                 }
             }
-            Halt => return new_state,
+            Halt => return (RunResult::Halted((label, registers.into_map())), trace),
         }
     }
 
-    new_state
+    (
+        RunResult::StepLimitReached((label, registers.into_map())),
+        trace,
+    )
 }
 
 fn encode_pair1(x: &BigUint, y: &BigUint) -> BigUint {
@@ -225,3 +450,13 @@ fn program_produces_correct_state() {
         )
     )
 }
+
+#[test]
+fn eval_program_omits_registers_never_reached() {
+    // Register 9 is only referenced by an instruction the run never
+    // reaches, so it must stay absent from the output state, not show up
+    // as a stray zero entry from the dense register store.
+    let program = vec![Halt, Add(9, 0)];
+    let final_state = eval_program(&program, &(0, HashMap::new()));
+    assert_eq!(final_state, (0, HashMap::new()));
+}
@@ -0,0 +1,287 @@
+//! A `serde`-free, RLP-style binary codec for programs and Gödel lists.
+//!
+//! Encodes a program or a Gödel list into a compact byte buffer using
+//! recursive-length-prefix framing:
+//!
+//! - a single byte `< 0x80` encodes itself;
+//! - a byte string of length 0-55 is prefixed by `0x80 + len`;
+//! - a longer byte string is prefixed by `0xb7 + len_of_len`, the
+//!   big-endian length, then the bytes;
+//! - a list payload of 0-55 bytes is prefixed by `0xc0 + len`;
+//! - a longer list payload is prefixed by `0xf7 + len_of_len`, the
+//!   big-endian length, then the payload.
+//!
+//! Each instruction is encoded as a list of its register/label integers:
+//! `Halt` as an empty list, `Add` as a 2-element list, `Sub` as a
+//! 3-element list. A program is the outer list of those lists.
+
+use num_bigint::BigUint;
+
+use crate::Instruction::{self, *};
+use crate::{Label, Register};
+
+/// An error produced while decoding an RLP byte buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The buffer ended before a length-prefixed item was fully read.
+    UnexpectedEof,
+    /// An integer (register/label) didn't fit the type it decodes into.
+    IntegerTooLarge,
+    /// A decoded list didn't have 0, 2, or 3 elements, so it can't be an
+    /// `Add`/`Sub`/`Halt` instruction.
+    InvalidInstruction(usize),
+    /// A decoded item had the wrong shape: a byte string where a list was
+    /// expected, or a list where an integer's byte string was expected.
+    ExpectedList,
+    /// The buffer had bytes left over after decoding the outer item.
+    TrailingBytes,
+}
+
+/// A decoded RLP value: either a byte string or a list of further items.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum RlpItem {
+    Bytes(Vec<u8>),
+    List(Vec<RlpItem>),
+}
+
+fn encode_header(short_base: u8, long_base: u8, len: usize) -> Vec<u8> {
+    if len <= 55 {
+        vec![short_base + len as u8]
+    } else {
+        let len_bytes = len.to_be_bytes();
+        let first_nonzero = len_bytes.iter().position(|&b| b != 0).unwrap();
+        let len_bytes = &len_bytes[first_nonzero..];
+
+        let mut header = vec![long_base + len_bytes.len() as u8];
+        header.extend_from_slice(len_bytes);
+        header
+    }
+}
+
+fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        return vec![bytes[0]];
+    }
+
+    let mut out = encode_header(0x80, 0xb7, bytes.len());
+    out.extend_from_slice(bytes);
+    out
+}
+
+fn encode_list(items: &[RlpItem]) -> Vec<u8> {
+    let payload: Vec<u8> = items.iter().flat_map(encode_item).collect();
+    let mut out = encode_header(0xc0, 0xf7, payload.len());
+    out.extend(payload);
+    out
+}
+
+fn encode_item(item: &RlpItem) -> Vec<u8> {
+    match item {
+        RlpItem::Bytes(bytes) => encode_bytes(bytes),
+        RlpItem::List(items) => encode_list(items),
+    }
+}
+
+fn take(input: &[u8], len: usize) -> Result<(&[u8], &[u8]), DecodeError> {
+    if input.len() < len {
+        Err(DecodeError::UnexpectedEof)
+    } else {
+        Ok(input.split_at(len))
+    }
+}
+
+fn be_bytes_to_len(bytes: &[u8]) -> Result<usize, DecodeError> {
+    // `len_of_len` is at most 8 (from the `0xb7 + len_of_len` / `0xf7 +
+    // len_of_len` long-form headers), so the big-endian value always fits
+    // in a `u128` without truncation; only the final narrowing to `usize`
+    // can fail, and that's checked explicitly.
+    let mut len: u128 = 0;
+    for &byte in bytes {
+        len = (len << 8) | byte as u128;
+    }
+    usize::try_from(len).map_err(|_| DecodeError::IntegerTooLarge)
+}
+
+fn decode_item(input: &[u8]) -> Result<(RlpItem, &[u8]), DecodeError> {
+    let &first = input.first().ok_or(DecodeError::UnexpectedEof)?;
+    let rest = &input[1..];
+
+    match first {
+        0x00..=0x7f => Ok((RlpItem::Bytes(vec![first]), rest)),
+        0x80..=0xb7 => {
+            let (bytes, rest) = take(rest, (first - 0x80) as usize)?;
+            Ok((RlpItem::Bytes(bytes.to_vec()), rest))
+        }
+        0xb8..=0xbf => {
+            let (len_bytes, rest) = take(rest, (first - 0xb7) as usize)?;
+            let (bytes, rest) = take(rest, be_bytes_to_len(len_bytes)?)?;
+            Ok((RlpItem::Bytes(bytes.to_vec()), rest))
+        }
+        0xc0..=0xf7 => {
+            let (payload, rest) = take(rest, (first - 0xc0) as usize)?;
+            Ok((RlpItem::List(decode_list_payload(payload)?), rest))
+        }
+        0xf8..=0xff => {
+            let (len_bytes, rest) = take(rest, (first - 0xf7) as usize)?;
+            let (payload, rest) = take(rest, be_bytes_to_len(len_bytes)?)?;
+            Ok((RlpItem::List(decode_list_payload(payload)?), rest))
+        }
+    }
+}
+
+fn decode_list_payload(mut payload: &[u8]) -> Result<Vec<RlpItem>, DecodeError> {
+    let mut items = Vec::new();
+    while !payload.is_empty() {
+        let (item, rest) = decode_item(payload)?;
+        items.push(item);
+        payload = rest;
+    }
+    Ok(items)
+}
+
+fn biguint_to_item(n: &BigUint) -> RlpItem {
+    RlpItem::Bytes(n.to_bytes_be())
+}
+
+fn item_to_biguint(item: &RlpItem) -> Result<BigUint, DecodeError> {
+    match item {
+        RlpItem::Bytes(bytes) => Ok(BigUint::from_bytes_be(bytes)),
+        RlpItem::List(_) => Err(DecodeError::ExpectedList),
+    }
+}
+
+fn instruction_to_item(instr: &Instruction) -> RlpItem {
+    match instr {
+        Add(r, l) => RlpItem::List(vec![
+            biguint_to_item(&BigUint::from(*r)),
+            biguint_to_item(&BigUint::from(*l as u64)),
+        ]),
+        Sub(r, l1, l2) => RlpItem::List(vec![
+            biguint_to_item(&BigUint::from(*r)),
+            biguint_to_item(&BigUint::from(*l1 as u64)),
+            biguint_to_item(&BigUint::from(*l2 as u64)),
+        ]),
+        Halt => RlpItem::List(vec![]),
+    }
+}
+
+fn item_to_register(item: &RlpItem) -> Result<Register, DecodeError> {
+    use num_traits::ToPrimitive;
+    item_to_biguint(item)?
+        .to_u64()
+        .ok_or(DecodeError::IntegerTooLarge)
+}
+
+fn item_to_label(item: &RlpItem) -> Result<Label, DecodeError> {
+    use num_traits::ToPrimitive;
+    item_to_biguint(item)?
+        .to_usize()
+        .ok_or(DecodeError::IntegerTooLarge)
+}
+
+fn item_to_instruction(item: &RlpItem) -> Result<Instruction, DecodeError> {
+    match item {
+        RlpItem::List(elems) => match elems.as_slice() {
+            [] => Ok(Halt),
+            [r, l] => Ok(Add(item_to_register(r)?, item_to_label(l)?)),
+            [r, l1, l2] => Ok(Sub(
+                item_to_register(r)?,
+                item_to_label(l1)?,
+                item_to_label(l2)?,
+            )),
+            other => Err(DecodeError::InvalidInstruction(other.len())),
+        },
+        RlpItem::Bytes(_) => Err(DecodeError::ExpectedList),
+    }
+}
+
+/// Encode a program into a compact RLP byte buffer.
+pub fn encode_program_rlp(program: &[Instruction]) -> Vec<u8> {
+    let items: Vec<RlpItem> = program.iter().map(instruction_to_item).collect();
+    encode_list(&items)
+}
+
+/// Decode a program previously encoded with [`encode_program_rlp`].
+pub fn decode_program_rlp(bytes: &[u8]) -> Result<Vec<Instruction>, DecodeError> {
+    let (item, rest) = decode_item(bytes)?;
+    if !rest.is_empty() {
+        return Err(DecodeError::TrailingBytes);
+    }
+    match item {
+        RlpItem::List(items) => items.iter().map(item_to_instruction).collect(),
+        RlpItem::Bytes(_) => Err(DecodeError::ExpectedList),
+    }
+}
+
+/// Encode a Gödel list (as produced by `decode_godel_to_list`) into a
+/// compact RLP byte buffer.
+pub fn encode_godel_list_rlp(godel_list: &[BigUint]) -> Vec<u8> {
+    let items: Vec<RlpItem> = godel_list.iter().map(biguint_to_item).collect();
+    encode_list(&items)
+}
+
+/// Decode a Gödel list previously encoded with [`encode_godel_list_rlp`].
+pub fn decode_godel_list_rlp(bytes: &[u8]) -> Result<Vec<BigUint>, DecodeError> {
+    let (item, rest) = decode_item(bytes)?;
+    if !rest.is_empty() {
+        return Err(DecodeError::TrailingBytes);
+    }
+    match item {
+        RlpItem::List(items) => items.iter().map(item_to_biguint).collect(),
+        RlpItem::Bytes(_) => Err(DecodeError::ExpectedList),
+    }
+}
+
+#[test]
+fn round_trips_empty_program() {
+    let encoded = encode_program_rlp(&[]);
+    assert_eq!(decode_program_rlp(&encoded), Ok(vec![]));
+}
+
+#[test]
+fn round_trips_mixed_program() {
+    let program = vec![Sub(0, 2, 1), Halt, Sub(0, 0, 1), Add(0, 0)];
+    let encoded = encode_program_rlp(&program);
+    assert_eq!(decode_program_rlp(&encoded), Ok(program));
+}
+
+#[test]
+fn round_trips_godel_list() {
+    use num_traits::Zero;
+    let godel_list = vec![
+        BigUint::from(46u32),
+        BigUint::zero(),
+        BigUint::from(10u32),
+        BigUint::from(1u32),
+    ];
+    let encoded = encode_godel_list_rlp(&godel_list);
+    assert_eq!(decode_godel_list_rlp(&encoded), Ok(godel_list));
+}
+
+#[test]
+fn rejects_trailing_bytes() {
+    let mut encoded = encode_program_rlp(&[Halt]);
+    encoded.push(0x00);
+    assert_eq!(decode_program_rlp(&encoded), Err(DecodeError::TrailingBytes));
+}
+
+#[test]
+fn encodes_long_programs_with_long_form_header() {
+    // 20 `Add` instructions need more than 55 payload bytes, so the outer
+    // list header must use the long form (0xf7 + len_of_len).
+    let program = vec![Add(0, 0); 20];
+    let encoded = encode_program_rlp(&program);
+    assert!(encoded[0] > 0xf7);
+    assert_eq!(decode_program_rlp(&encoded), Ok(program));
+}
+
+#[test]
+fn be_bytes_to_len_rejects_a_length_too_large_for_usize() {
+    // More bytes than any platform's `usize` can hold, so the length must be
+    // rejected rather than truncated.
+    let bytes = [0xff; 9];
+    assert_eq!(
+        be_bytes_to_len(&bytes),
+        Err(DecodeError::IntegerTooLarge)
+    );
+}
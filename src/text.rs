@@ -0,0 +1,194 @@
+//! Human-readable I/O for Gödel numbers and programs.
+//!
+//! Gödel numbers are otherwise only built and inspected as raw `BigUint`s
+//! constructed with `pow`, which is painful to enter or read back. This
+//! module adds a radix-based parser/printer for the numbers themselves,
+//! plus an assembly-style textual format for programs, e.g.:
+//!
+//! ```text
+//! L0: SUB r0 -> L2, L1
+//! L1: HALT
+//! L2: SUB r0 -> L0, L1
+//! L3: ADD r0 -> L0
+//! ```
+
+use std::fmt;
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use crate::Instruction::{self, *};
+use crate::{Label, Register};
+
+/// An error produced while parsing a Gödel number from a string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GodelParseError {
+    /// `c` isn't a valid digit in the given radix.
+    InvalidDigit(char),
+}
+
+/// Parse a Gödel number from a string in the given radix (e.g. 16 for
+/// hex), shifting each digit's value into the big integer as it goes.
+pub fn godel_from_str_radix(s: &str, radix: u32) -> Result<BigUint, GodelParseError> {
+    let radix_big = BigUint::from(radix);
+    let mut acc = BigUint::zero();
+
+    for c in s.chars() {
+        let digit = c.to_digit(radix).ok_or(GodelParseError::InvalidDigit(c))?;
+        acc = acc * &radix_big + BigUint::from(digit);
+    }
+
+    Ok(acc)
+}
+
+/// Print a Gödel number in the given radix (e.g. 16 for hex).
+pub fn godel_to_str_radix(n: &BigUint, radix: u32) -> String {
+    n.to_str_radix(radix)
+}
+
+/// An error produced while parsing a program in the assembly-style text
+/// format (see the module documentation for the grammar).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// A line gave an `Lx:` prefix, but `x` wasn't the expected index.
+    LabelMismatch { expected: usize, found: String },
+    /// A line's instruction body didn't match `ADD`/`SUB`/`HALT` syntax.
+    MalformedInstruction(String),
+}
+
+/// Parse a program written in the assembly-style text format, one
+/// instruction per line:
+///
+/// - `Lx: ADD r<register> -> L<label>`
+/// - `Lx: SUB r<register> -> L<label_if_nonzero>, L<label_if_zero>`
+/// - `Lx: HALT`
+///
+/// where `x` must match the instruction's position in the program. The
+/// `Lx:` prefix is optional — a line's position already fixes its label,
+/// so a bare `HALT` (or any other instruction written without a prefix)
+/// parses the same as one with its own `Lx:`.
+pub fn parse_program(s: &str) -> Result<Vec<Instruction>, ParseError> {
+    s.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .enumerate()
+        .map(|(index, line)| parse_line(index, line))
+        .collect()
+}
+
+fn parse_line(index: usize, line: &str) -> Result<Instruction, ParseError> {
+    match line.split_once(':') {
+        Some((label, body)) => {
+            let label = label.trim();
+            let expected_label = format!("L{index}");
+            if label != expected_label {
+                return Err(ParseError::LabelMismatch {
+                    expected: index,
+                    found: label.to_string(),
+                });
+            }
+            parse_instruction_body(body.trim())
+        }
+        None => parse_instruction_body(line.trim()),
+    }
+}
+
+fn parse_label(s: &str) -> Result<Label, ParseError> {
+    s.trim()
+        .strip_prefix('L')
+        .and_then(|digits| digits.parse().ok())
+        .ok_or_else(|| ParseError::MalformedInstruction(s.to_string()))
+}
+
+fn parse_register(s: &str) -> Result<Register, ParseError> {
+    s.trim()
+        .strip_prefix('r')
+        .and_then(|digits| digits.parse().ok())
+        .ok_or_else(|| ParseError::MalformedInstruction(s.to_string()))
+}
+
+fn parse_instruction_body(body: &str) -> Result<Instruction, ParseError> {
+    if body == "HALT" {
+        return Ok(Halt);
+    }
+
+    let (mnemonic, rest) = body
+        .split_once(' ')
+        .ok_or_else(|| ParseError::MalformedInstruction(body.to_string()))?;
+    let (register, targets) = rest
+        .split_once("->")
+        .ok_or_else(|| ParseError::MalformedInstruction(body.to_string()))?;
+    let register = parse_register(register)?;
+
+    match mnemonic {
+        "ADD" => Ok(Add(register, parse_label(targets)?)),
+        "SUB" => {
+            let (l1, l2) = targets
+                .split_once(',')
+                .ok_or_else(|| ParseError::MalformedInstruction(body.to_string()))?;
+            Ok(Sub(register, parse_label(l1)?, parse_label(l2)?))
+        }
+        _ => Err(ParseError::MalformedInstruction(body.to_string())),
+    }
+}
+
+/// Wraps a program slice so it can be printed in the assembly-style text
+/// format accepted by [`parse_program`].
+pub struct Program<'a>(pub &'a [Instruction]);
+
+impl fmt::Display for Program<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, instr) in self.0.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "L{index}: ")?;
+            match instr {
+                Add(r, l) => write!(f, "ADD r{r} -> L{l}")?,
+                Sub(r, l1, l2) => write!(f, "SUB r{r} -> L{l1}, L{l2}")?,
+                Halt => write!(f, "HALT")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn godel_number_round_trips_through_hex() {
+    let n = BigUint::from(2u32).pow(46u32) * 20483u32;
+    let s = godel_to_str_radix(&n, 16);
+    assert_eq!(godel_from_str_radix(&s, 16), Ok(n));
+}
+
+#[test]
+fn godel_from_str_radix_rejects_bad_digit() {
+    assert_eq!(
+        godel_from_str_radix("12g", 16),
+        Err(GodelParseError::InvalidDigit('g'))
+    );
+}
+
+#[test]
+fn program_round_trips_through_text_format() {
+    let program = vec![Sub(0, 2, 1), Halt, Sub(0, 0, 1), Add(0, 0)];
+    let text = Program(&program).to_string();
+    assert_eq!(text, "L0: SUB r0 -> L2, L1\nL1: HALT\nL2: SUB r0 -> L0, L1\nL3: ADD r0 -> L0");
+    assert_eq!(parse_program(&text), Ok(program));
+}
+
+#[test]
+fn parse_program_accepts_a_bare_terminal_halt() {
+    let program = parse_program("L0: ADD r0 -> L1\nHALT").unwrap();
+    assert_eq!(program, vec![Add(0, 1), Halt]);
+}
+
+#[test]
+fn parse_program_rejects_label_mismatch() {
+    assert_eq!(
+        parse_program("L1: HALT"),
+        Err(ParseError::LabelMismatch {
+            expected: 0,
+            found: "L1".to_string(),
+        })
+    );
+}